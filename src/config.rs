@@ -0,0 +1,172 @@
+/*!
+ * Layout configuration loading.
+ *
+ * A `Layout` can be described in JSON, YAML, or TOML. The format is picked by the
+ * base file's extension. On top of the base file, callers may layer an optional
+ * environment-specific overlay file and finally environment-variable overrides,
+ * so the same layout definition can move from dev to prod without editing files.
+ */
+use std::env;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::source::read_source_to_string;
+use crate::Layout;
+
+/// Name of the environment variable used to select the overlay file, e.g.
+/// `FITLE_ENV=prod` applied to `layout.json` looks for `layout.prod.json`.
+const ENV_SELECTOR_VAR: &str = "FITLE_ENV";
+
+/// Environment variables that, when set, override the matching `Layout` field
+/// after the base file and overlay have been merged.
+const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("FITLE_DESTINATION", "destination"),
+    ("FITLE_STORAGE_NAME", "storage_name"),
+    ("FITLE_DELIMITER", "delimiter"),
+];
+
+/// Loads a `Layout` from `base_location`, layering an optional environment
+/// overlay and environment-variable overrides on top.
+///
+/// # Parameters
+///
+/// - base_location: Path or URL to the base layout file (JSON, YAML, or TOML).
+///
+/// # Returns
+///
+/// The merged `Layout`.
+///
+/// # Example
+///
+/// ```
+/// let layout = load_layout(&layout_file).await?;
+/// ```
+///
+pub(crate) async fn load_layout(base_location: &str) -> Result<Layout, Box<dyn Error>> {
+    let mut merged = parse_value(base_location, &read_source_to_string(base_location).await?)?;
+
+    if let Some(overlay_location) = overlay_location(base_location) {
+        if let Ok(overlay_text) = read_source_to_string(&overlay_location).await {
+            let overlay = parse_value(&overlay_location, &overlay_text)?;
+            deep_merge(&mut merged, overlay);
+        }
+    }
+
+    deep_merge(&mut merged, env_overrides());
+
+    Ok(serde_json::from_value(merged)?)
+}
+
+/// Parses `text` into a `serde_json::Value`, dispatching on the extension of
+/// `location` (`.json`, `.yaml`/`.yml`, or `.toml`). Defaults to JSON.
+fn parse_value(location: &str, text: &str) -> Result<Value, Box<dyn Error>> {
+    match extension_of(location).as_deref() {
+        Some("yaml") | Some("yml") => Ok(serde_yaml::from_str(text)?),
+        Some("toml") => Ok(toml::from_str(text)?),
+        _ => Ok(serde_json::from_str(text)?),
+    }
+}
+
+fn extension_of(location: &str) -> Option<String> {
+    Path::new(location)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+}
+
+/// Builds the path of the environment-specific overlay, derived from
+/// `FITLE_ENV` (e.g. `layout.json` + `FITLE_ENV=prod` -> `layout.prod.json`).
+fn overlay_location(base_location: &str) -> Option<String> {
+    let env_name = env::var(ENV_SELECTOR_VAR).ok()?;
+    let base_path = PathBuf::from(base_location);
+    let extension = base_path.extension()?.to_string_lossy().to_string();
+    let stem = base_path.file_stem()?.to_string_lossy().to_string();
+
+    let overlay_name = format!("{}.{}.{}", stem, env_name, extension);
+    Some(
+        base_path
+            .with_file_name(overlay_name)
+            .to_string_lossy()
+            .to_string(),
+    )
+}
+
+/// Builds a `Value` map of the `FITLE_*` overrides that are currently set.
+fn env_overrides() -> Value {
+    let mut overrides = Map::new();
+    for (var, field) in ENV_OVERRIDES {
+        if let Ok(value) = env::var(var) {
+            overrides.insert(field.to_string(), Value::String(value));
+        }
+    }
+    Value::Object(overrides)
+}
+
+/// Deep-merges `overlay` into `base`: objects are merged key-by-key, with
+/// later sources overwriting earlier ones; arrays and scalars are replaced
+/// wholesale.
+fn deep_merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base_map), Value::Object(overlay_map)) => {
+            for (key, overlay_value) in overlay_map {
+                match base_map.get_mut(&key) {
+                    Some(base_value) => deep_merge(base_value, overlay_value),
+                    None => {
+                        base_map.insert(key, overlay_value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn overlay_field_overrides_base_field() {
+        let mut base = json!({"destination": "queue", "batch_size": 500});
+        deep_merge(&mut base, json!({"destination": "sql"}));
+        assert_eq!(base, json!({"destination": "sql", "batch_size": 500}));
+    }
+
+    #[test]
+    fn overlay_adds_new_field() {
+        let mut base = json!({"destination": "queue"});
+        deep_merge(&mut base, json!({"storage_name": "orders"}));
+        assert_eq!(base, json!({"destination": "queue", "storage_name": "orders"}));
+    }
+
+    #[test]
+    fn nested_objects_are_merged_key_by_key() {
+        let mut base = json!({"fields": {"a": {"min": 0, "max": 10}}});
+        deep_merge(&mut base, json!({"fields": {"a": {"max": 20}}}));
+        assert_eq!(base, json!({"fields": {"a": {"min": 0, "max": 20}}}));
+    }
+
+    #[test]
+    fn overlay_array_replaces_base_array_wholesale() {
+        let mut base = json!({"fields": [1, 2, 3]});
+        deep_merge(&mut base, json!({"fields": [9]}));
+        assert_eq!(base, json!({"fields": [9]}));
+    }
+
+    #[test]
+    fn empty_overlay_leaves_base_untouched() {
+        let mut base = json!({"destination": "queue"});
+        deep_merge(&mut base, json!({}));
+        assert_eq!(base, json!({"destination": "queue"}));
+    }
+
+    #[test]
+    fn env_overrides_apply_on_top_of_base_and_overlay() {
+        let mut merged = json!({"destination": "queue", "storage_name": "base-queue"});
+        deep_merge(&mut merged, json!({"storage_name": "overlay-queue"}));
+        deep_merge(&mut merged, json!({"storage_name": "env-queue"}));
+        assert_eq!(merged["storage_name"], json!("env-queue"));
+    }
+}
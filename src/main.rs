@@ -7,287 +7,233 @@
  * 
  * # Description: 
  * 
- * The Text File Interpreter is a Rust-based project that reads data from various file 
- * formats (CSV, fixed-width text files) and publishes the data to a RabbitMQ queue or 
- * stores it in a MongoDB collection. This project is designed to handle different data 
- * types and configurations, making it flexible and adaptable to various use cases.
+ * The Text File Interpreter is a Rust-based project that reads data from various file
+ * formats (CSV, fixed-width text files) and writes the data to one or more pluggable
+ * destinations (a RabbitMQ queue, a MongoDB collection, both, or a SQL table) via the
+ * `Sink` trait.
+ * This project is designed to handle different data types and configurations, making
+ * it flexible and adaptable to various use cases.
  *
  */
 use std::env;
 use std::error::Error;
-use std::fs;
 use std::collections::HashMap;
-use std::io::BufRead;
-use std::io::BufReader;
 use serde::{Serialize, Deserialize};
-use serde_json::json;
-use lapin::{options::*, types::FieldTable, BasicProperties, Connection, ConnectionProperties};
-use log::{info, error};
-use env_logger;
+use log::error;
 use dotenv::dotenv;
-use mongodb::{Client, options::ClientOptions};
-use mongodb::bson::Document;
+
+mod config;
+mod pipeline;
+mod sinks;
+mod source;
+mod validation;
+
+use config::load_layout;
+use pipeline::{csv_records, fixed_records, ParseError};
+use sinks::build_sink;
+use validation::ValidationError;
 
 #[derive(Serialize, Deserialize, Debug)]
-enum FieldType {
+pub(crate) enum FieldType {
     Fixed,
-    Delimited, 
+    Delimited,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub(crate) enum OnErrorPolicy {
+    #[serde(rename = "skip")]
+    Skip,
+    #[serde(rename = "fail")]
+    Fail,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-struct Layout {
+pub(crate) struct Layout {
     name: String,
     version: usize,
-    delimiter: Option<char>, 
+    delimiter: Option<char>,
     file_type: FieldType,
-    destination: String, 
-    storage_name: String, 
+    destination: String,
+    storage_name: String,
+    on_error: Option<OnErrorPolicy>,
+    batch_size: Option<usize>,
     fields: Vec<Field>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-struct Field {
+pub(crate) struct Field {
     name: String,
-    description: String, 
+    description: String,
     position: usize,
     size: usize,
     field_type: String,
+    required: Option<bool>,
+    regex: Option<String>,
+    min: Option<f64>,
+    max: Option<f64>,
+    default: Option<serde_json::Value>,
 }
 
 #[derive(Debug)]
-struct Record {
+pub(crate) struct Record {
+    // Unread with zero sink features enabled (the default build); every
+    // `Sink` implementation reads it.
+    #[allow(dead_code)]
     fields: HashMap<String, serde_json::Value>
 }
 
-/// Reads the file containing the layout settings.
-/// 
-/// # Parameters
-/// 
-/// - file_name: Name of the file containing the layout for data extraction.
-/// 
-/// # Returns
-/// 
-/// A vector of `Layout` structs representing the layout.
-/// 
-/// # Example
-/// 
-/// ```
-/// let layout = read_config_json(&layout_file);
-/// ```
-/// 
-fn read_config_json(file_name: &str) -> Result<Layout, Box<dyn Error>> {
-    let config = fs::read_to_string(file_name)?;
-    let layout: Layout = serde_json::from_str(&config)?;
-    Ok(layout)
-}
+/// Default number of records accumulated before a batch is handed to the sink.
+const DEFAULT_BATCH_SIZE: usize = 500;
 
-/// Reads the CSV data file and extracts the lines based on the provided layout.
-/// 
-/// # Parameters
-/// 
-/// - file_name: Name of the CSV data file.
-/// - layout: A slice of `Field` structs representing the layout.
-/// 
+/// Consumes `records` in batches of `batch_size`, writing each batch to
+/// `sink` as soon as it fills up rather than collecting the whole file in
+/// memory. Validation violations are logged as they're found; when
+/// `on_error` is `Fail`, the first violation aborts the run. Note that
+/// batches already written to the sink before the abort are not rolled
+/// back, since streaming trades the old all-or-nothing write for bounded
+/// memory use.
+///
 /// # Returns
-/// 
-/// A vector of `Record` structs containing the extracted data.
-/// 
-/// # Example
-/// 
-/// ```
-/// let records = read_csv_data("data.csv", "layout.txt");
-/// ```
-/// 
-fn read_csv_data(file_name: &str, layout: &Layout) -> Vec<Record> {
-    let file = fs::File::open(file_name).expect("Unable to open data file");
-    let reader = BufReader::new(file);
-    let mut records = Vec::new();
-    let delimiter = layout.delimiter.unwrap_or(',');
-
-    for line in reader.lines() {
-        let line = line.expect("Unable to read line");
-        let mut fields = HashMap::new();
-        let values: Vec<&str> = line.split(delimiter).collect();
-
-        for (i, field) in layout.fields.iter().enumerate() {
-            if let Some(value) = values.get(i) {
-                let value = value.trim().trim_matches('"');
-                let json_value = match field.field_type.as_str() {
-                    "string" => serde_json::Value::String(value.to_string()),
-                    "int" => serde_json::Value::Number(value.parse::<i64>().unwrap_or(0).into()),
-                    "float" => serde_json::Value::Number(serde_json::Number::from_f64(value.parse::<f64>().unwrap_or(0.0)).unwrap()),
-                    "bool" => serde_json::Value::Bool(value.parse::<bool>().unwrap_or(false)),
-                    _ => serde_json::Value::String(value.to_string()),
-                };
-                fields.insert(field.name.clone(), json_value);
+///
+/// The total number of records written.
+async fn process_records(
+    records: impl Iterator<Item = Result<(Record, Vec<ValidationError>), ParseError>>,
+    sink: &dyn sinks::Sink,
+    batch_size: usize,
+    on_error: OnErrorPolicy,
+) -> Result<usize, Box<dyn Error>> {
+    let mut batch = Vec::new();
+    let mut total = 0;
+
+    for item in records {
+        let (record, violations) = item?;
+        for violation in &violations {
+            error!(
+                "line {}: field '{}': {}",
+                violation.line, violation.field, violation.reason
+            );
+            if matches!(on_error, OnErrorPolicy::Fail) {
+                return Err(format!(
+                    "aborting on validation violation at line {} (on_error: fail)",
+                    violation.line
+                )
+                .into());
             }
         }
-        records.push(Record { fields });
-    }
-    records
-}
 
-/// Reads the data file and extracts the lines based on the provided layout.
-/// 
-/// # Parameters
-/// 
-/// - file_name: Name of the data file.
-/// - layout: A slice of `Field` structs representing the layout.
-/// 
-/// # Returns
-/// 
-/// A vector of `Record` structs containing the extracted data.
-/// 
-/// # Example
-/// 
-/// ```
-/// let records = read_fixed_data("data.txt", "layout.txt");
-/// ```
-/// 
-fn read_fixed_data(file_name: &str, layout: &Layout) -> Vec<Record> {
-    let file = fs::File::open(file_name).expect("Unable to open data file");
-    let reader = BufReader::new(file);
-    let mut records = Vec::new();
-    let mut lines = reader.lines();
-
-    while let Some(line) = lines.next() {
-        let line = line.expect("Unable to read line");
-        let mut fields = HashMap::new();
-        let mut current_line = line.clone();
-        let mut current_pos  = 0;
-
-        for field in &layout.fields {
-            if field.position < current_pos {
-                if let Some(next_line) = lines.next() {
-                    current_line = next_line.expect("Unable to read line");
-                } else {
-                    break;
-                }
-            }
-            let value = current_line[field.position -1 .. field.position -1 + field.size].trim().to_string();
-            let json_value = match field.field_type.as_str() {
-                "string" => serde_json::Value::String(value.to_string()),
-                "int" => serde_json::Value::Number(value.parse::<i64>().unwrap_or(0).into()),
-                "float" => serde_json::Value::Number(serde_json::Number::from_f64(value.parse::<f64>().unwrap_or(0.0)).unwrap()),
-                "bool" => serde_json::Value::Bool(value.parse::<bool>().unwrap_or(false)),
-                _ => serde_json::Value::String(value.to_string()),
-            };
-            fields.insert(field.name.clone(), json_value);
-            current_pos = field.position + field.size -1;
+        batch.push(record);
+        if batch.len() >= batch_size {
+            sink.write(&batch).await?;
+            total += batch.len();
+            batch.clear();
         }
-        records.push(Record { fields });
     }
-    records
-}
 
-/// Sends the JSON output to a message queue.
-///
-/// # Parameters
-///
-/// - `json_output`: The JSON string containing the records.
-///
-/// # Example
-///
-/// ```
-/// send_to_queue(&json_output);
-/// ```
-async fn send_to_queue(json_output: &str, queue_name: &str) {
-    let addr = std::env::var("AMQP_ADDR").expect("AMQP_ADDR not set in .env file");
-    let conn = Connection::connect(&addr, ConnectionProperties::default()).await.expect("Connection error");
-
-    let channel = conn.create_channel().await.expect("Create channel error");
-    channel.queue_declare(queue_name
-                         , QueueDeclareOptions::default()
-                         , FieldTable::default(),
-    ).await.expect("Queue declare error");
-
-    channel.basic_publish(""
-                         , queue_name
-                         , BasicPublishOptions::default()
-                         , json_output.as_bytes()
-                         , BasicProperties::default().with_delivery_mode(1),
-    ).await.expect("Basic publish error");
-    info!("Sent records to RabbitMQ queue: {}", queue_name);
-}
-
-/// Saves the JSON output to a MongoDB database.
-///
-/// # Parameters
-///
-/// - `json_output`: The JSON string containing the records.
-///
-/// # Example
-///
-/// ```
-/// save_to_mongodb(&json_output);
-/// ```
-async fn save_to_mongodb(json_output: &str, collection_name: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let client_options = ClientOptions::parse(&env::var("MONGODB_URI").expect("MONGODB_URI not set in .env file")).await?;
-    let client = Client::with_options(client_options)?;
-    let database = client.database("mydb");
-    let collection = database.collection::<Document>(collection_name);
-
-    let docs: Vec<mongodb::bson::Document> = serde_json::from_str(json_output)?;
-    match collection.insert_many(docs, None).await {
-        Ok(_) => {
-            info!("Saved records to MongoDB collection: {}", collection_name);
-            Ok(())
-        },
-        Err(e) => Err(Box::new(e))
+    if !batch.is_empty() {
+        total += batch.len();
+        sink.write(&batch).await?;
     }
+    Ok(total)
 }
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     env_logger::init();
     dotenv().ok();
 
     // Check parameters
-    if env::args().len() < 2 {
+    if env::args().len() < 3 {
         error!("Program requires two arguments <layout file> <data file>");
-        return
+        return Err("missing required arguments: <layout file> <data file>".into());
     }
 
     // Reading the parameters
-    let layout_file: String = env::args().nth(1).unwrap();
-    let data_file: String = env::args().nth(2).unwrap();
+    let layout_file: String = env::args().nth(1).ok_or("missing <layout file> argument")?;
+    let data_file: String = env::args().nth(2).ok_or("missing <data file> argument")?;
 
     // Reads configuration and data files
-    let layout = read_config_json(&layout_file).expect("Unable to read layout file");
+    let layout = load_layout(&layout_file).await?;
+    let batch_size = layout.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+    let on_error = layout.on_error.unwrap_or(OnErrorPolicy::Skip);
+    let sink = build_sink(&layout)?;
 
-    let records = match layout.file_type {
+    let total = match layout.file_type {
         FieldType::Delimited => {
-            read_csv_data(&data_file, &layout) 
-        },
+            process_records(csv_records(&data_file, &layout).await?, sink.as_ref(), batch_size, on_error).await?
+        }
         FieldType::Fixed => {
-            read_fixed_data(&data_file, &layout)
+            process_records(fixed_records(&data_file, &layout).await?, sink.as_ref(), batch_size, on_error).await?
         }
     };
-    
-    // Convert records to json format
-    let json_records: Vec<_> = records.iter().map(|record| json!(record.fields)).collect();
-    let json_output = serde_json::to_string_pretty(&json_records).unwrap();
-    println!("Processed records: {}", json_output);
-    
-    // Convert records to JSON format and send to RabbitMQ queue
-    match layout.destination.as_str() {
-        "queue" => {
-            for record in records {
-                let json_record = serde_json::to_string(&record.fields).unwrap();
-                send_to_queue(&json_record, &layout.storage_name).await;
-            }    
-        }, 
-        "both" => {
-            for record in records {
-                let json_record = serde_json::to_string(&record.fields).unwrap();
-                send_to_queue(&json_record, &layout.storage_name).await;
-            };    
-            save_to_mongodb(&json_output, &layout.storage_name).await.unwrap();
+
+    println!("Processed {} record(s) for destination '{}'", total, layout.destination);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct MockSink {
+        batches: Mutex<Vec<usize>>,
+    }
+
+    impl MockSink {
+        fn new() -> Self {
+            MockSink { batches: Mutex::new(Vec::new()) }
         }
-        "repository" => {
-            save_to_mongodb(&json_output, &layout.storage_name).await.unwrap();
-        },
-        _ => error!("Invalid destination specified in config file")
     }
 
+    #[async_trait::async_trait]
+    impl sinks::Sink for MockSink {
+        async fn write(&self, records: &[Record]) -> Result<(), Box<dyn Error>> {
+            self.batches.lock().unwrap().push(records.len());
+            Ok(())
+        }
+    }
+
+    fn ok_record() -> Result<(Record, Vec<ValidationError>), ParseError> {
+        Ok((Record { fields: HashMap::new() }, Vec::new()))
+    }
+
+    fn violating_record(line: usize) -> Result<(Record, Vec<ValidationError>), ParseError> {
+        Ok((
+            Record { fields: HashMap::new() },
+            vec![ValidationError { line, field: "amount".to_string(), reason: "bad value".to_string() }],
+        ))
+    }
+
+    #[tokio::test]
+    async fn flushes_a_batch_as_soon_as_it_fills_up() {
+        let sink = MockSink::new();
+        let records = vec![ok_record(), ok_record(), ok_record()];
+
+        let total = process_records(records.into_iter(), &sink, 2, OnErrorPolicy::Skip).await.unwrap();
+
+        assert_eq!(total, 3);
+        assert_eq!(*sink.batches.lock().unwrap(), vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn skip_policy_keeps_processing_past_a_violation() {
+        let sink = MockSink::new();
+        let records = vec![ok_record(), violating_record(3), ok_record()];
+
+        let total = process_records(records.into_iter(), &sink, 10, OnErrorPolicy::Skip).await.unwrap();
+
+        assert_eq!(total, 3);
+        assert_eq!(*sink.batches.lock().unwrap(), vec![3]);
+    }
+
+    #[tokio::test]
+    async fn fail_policy_aborts_on_the_first_violation_but_keeps_prior_batches() {
+        let sink = MockSink::new();
+        let records = vec![ok_record(), ok_record(), violating_record(5), ok_record()];
+
+        let err = process_records(records.into_iter(), &sink, 2, OnErrorPolicy::Fail).await.unwrap_err();
+
+        assert!(err.to_string().contains("line 5"));
+        assert_eq!(*sink.batches.lock().unwrap(), vec![2]);
+    }
 }
@@ -0,0 +1,306 @@
+/*!
+ * Streaming record extraction.
+ *
+ * `csv_records`/`fixed_records` return iterators of
+ * `Result<(Record, Vec<ValidationError>), ParseError>` instead of reading the
+ * whole file into a `Vec<Record>` up front, so a multi-GB file is never held
+ * in memory at once. A malformed value surfaces as `ValidationError`s yielded
+ * alongside its record, and a failure reading a line as a `ParseError` item,
+ * rather than aborting the process.
+ */
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::io::{BufRead, BufReader, Lines, Read};
+
+use regex::Regex;
+
+use crate::source::open_buffered;
+use crate::validation::{compile_field_regex, extract_field_value, ValidationError};
+use crate::{Layout, Record};
+
+/// Compiles each field's `regex`, if declared, once per layout so a pattern
+/// that never changes isn't recompiled for every line.
+fn compile_regexes(layout: &Layout) -> Result<Vec<Option<Regex>>, Box<dyn Error>> {
+    layout
+        .fields
+        .iter()
+        .map(|field| match compile_field_regex(field) {
+            Some(Ok(re)) => Ok(Some(re)),
+            Some(Err(e)) => Err(format!("invalid regex for field '{}': {}", field.name, e).into()),
+            None => Ok(None),
+        })
+        .collect()
+}
+
+/// A failure reading a line, as opposed to a field-level `ValidationError`
+/// (which is collected alongside a `Record` rather than aborting extraction
+/// of it). A short or truncated line is not a `ParseError`: it is routed
+/// through `extract_field_value` per-field like a missing CSV column, so
+/// `required`/`default`/`on_error` still apply to it.
+#[derive(Debug)]
+pub(crate) struct ParseError(io::Error);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "I/O error while reading data file: {}", self.0)
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<io::Error> for ParseError {
+    fn from(e: io::Error) -> Self {
+        ParseError(e)
+    }
+}
+
+/// Iterator over CSV/delimited records, yielded one line at a time.
+pub(crate) struct CsvRecords<'a> {
+    lines: Lines<BufReader<Box<dyn Read + Send>>>,
+    layout: &'a Layout,
+    regexes: Vec<Option<Regex>>,
+    delimiter: char,
+    line_number: usize,
+}
+
+impl<'a> Iterator for CsvRecords<'a> {
+    type Item = Result<(Record, Vec<ValidationError>), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = match self.lines.next()? {
+            Ok(line) => line,
+            Err(e) => return Some(Err(ParseError(e))),
+        };
+        self.line_number += 1;
+
+        let mut fields = std::collections::HashMap::new();
+        let mut violations = Vec::new();
+        let values: Vec<&str> = line.split(self.delimiter).collect();
+
+        for (i, field) in self.layout.fields.iter().enumerate() {
+            let compiled_regex = self.regexes[i].as_ref();
+            match extract_field_value(field, compiled_regex, values.get(i).copied(), self.line_number) {
+                Ok(value) => {
+                    fields.insert(field.name.clone(), value);
+                }
+                Err(violation) => violations.push(violation),
+            }
+        }
+        Some(Ok((Record { fields }, violations)))
+    }
+}
+
+/// Opens `file_name` and returns a streaming iterator of delimited records.
+pub(crate) async fn csv_records<'a>(file_name: &str, layout: &'a Layout) -> Result<CsvRecords<'a>, Box<dyn Error>> {
+    Ok(CsvRecords {
+        lines: open_buffered(file_name).await?.lines(),
+        regexes: compile_regexes(layout)?,
+        layout,
+        delimiter: layout.delimiter.unwrap_or(','),
+        line_number: 0,
+    })
+}
+
+/// Iterator over fixed-width records, which may span more than one line.
+pub(crate) struct FixedRecords<'a> {
+    lines: Lines<BufReader<Box<dyn Read + Send>>>,
+    layout: &'a Layout,
+    regexes: Vec<Option<Regex>>,
+    line_number: usize,
+}
+
+impl<'a> Iterator for FixedRecords<'a> {
+    type Item = Result<(Record, Vec<ValidationError>), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut current_line = match self.lines.next()? {
+            Ok(line) => line,
+            Err(e) => return Some(Err(ParseError(e))),
+        };
+        self.line_number += 1;
+
+        let mut fields = std::collections::HashMap::new();
+        let mut violations = Vec::new();
+        let mut current_pos = 0;
+
+        for (i, field) in self.layout.fields.iter().enumerate() {
+            if field.position < current_pos {
+                match self.lines.next() {
+                    Some(Ok(next_line)) => {
+                        current_line = next_line;
+                        self.line_number += 1;
+                    }
+                    Some(Err(e)) => return Some(Err(ParseError(e))),
+                    None => break,
+                }
+            }
+
+            let start = field.position - 1;
+            let end = start + field.size;
+            let value = if start >= current_line.len() {
+                None
+            } else {
+                Some(current_line[start..end.min(current_line.len())].trim())
+            };
+
+            let compiled_regex = self.regexes[i].as_ref();
+            match extract_field_value(field, compiled_regex, value, self.line_number) {
+                Ok(json_value) => {
+                    fields.insert(field.name.clone(), json_value);
+                }
+                Err(violation) => violations.push(violation),
+            }
+            current_pos = field.position + field.size - 1;
+        }
+        Some(Ok((Record { fields }, violations)))
+    }
+}
+
+/// Opens `file_name` and returns a streaming iterator of fixed-width records.
+pub(crate) async fn fixed_records<'a>(file_name: &str, layout: &'a Layout) -> Result<FixedRecords<'a>, Box<dyn Error>> {
+    Ok(FixedRecords {
+        lines: open_buffered(file_name).await?.lines(),
+        regexes: compile_regexes(layout)?,
+        layout,
+        line_number: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    use crate::FieldType;
+
+    fn field(name: &str, position: usize, size: usize) -> crate::Field {
+        crate::Field {
+            name: name.to_string(),
+            description: String::new(),
+            position,
+            size,
+            field_type: "string".to_string(),
+            required: None,
+            regex: None,
+            min: None,
+            max: None,
+            default: None,
+        }
+    }
+
+    fn layout(fields: Vec<crate::Field>, file_type: FieldType, delimiter: Option<char>) -> Layout {
+        Layout {
+            name: "test".to_string(),
+            version: 1,
+            delimiter,
+            file_type,
+            destination: "queue".to_string(),
+            storage_name: "test".to_string(),
+            on_error: None,
+            batch_size: None,
+            fields,
+        }
+    }
+
+    fn lines_of(text: &str) -> Lines<BufReader<Box<dyn Read + Send>>> {
+        let boxed: Box<dyn Read + Send> = Box::new(Cursor::new(text.as_bytes().to_vec()));
+        BufReader::new(boxed).lines()
+    }
+
+    #[test]
+    fn csv_records_splits_each_line_by_delimiter() {
+        let layout = layout(vec![field("a", 1, 0), field("b", 1, 0)], FieldType::Delimited, Some(','));
+        let mut records = CsvRecords {
+            lines: lines_of("x,y\n"),
+            regexes: compile_regexes(&layout).unwrap(),
+            layout: &layout,
+            delimiter: ',',
+            line_number: 0,
+        };
+
+        let (record, violations) = records.next().unwrap().unwrap();
+        assert!(violations.is_empty());
+        assert_eq!(record.fields["a"], serde_json::json!("x"));
+        assert_eq!(record.fields["b"], serde_json::json!("y"));
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn fixed_records_reads_a_single_line_when_positions_keep_advancing() {
+        let layout = layout(
+            vec![field("a", 1, 3), field("b", 4, 3)],
+            FieldType::Fixed,
+            None,
+        );
+        let mut records = FixedRecords {
+            lines: lines_of("fooBar\n"),
+            regexes: compile_regexes(&layout).unwrap(),
+            layout: &layout,
+            line_number: 0,
+        };
+
+        let (record, violations) = records.next().unwrap().unwrap();
+        assert!(violations.is_empty());
+        assert_eq!(record.fields["a"], serde_json::json!("foo"));
+        assert_eq!(record.fields["b"], serde_json::json!("Bar"));
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn fixed_records_reads_the_next_line_once_the_position_wraps_around() {
+        let layout = layout(
+            vec![field("a", 1, 3), field("b", 1, 3)],
+            FieldType::Fixed,
+            None,
+        );
+        let mut records = FixedRecords {
+            lines: lines_of("foo\nbar\n"),
+            regexes: compile_regexes(&layout).unwrap(),
+            layout: &layout,
+            line_number: 0,
+        };
+
+        let (record, violations) = records.next().unwrap().unwrap();
+        assert!(violations.is_empty());
+        assert_eq!(record.fields["a"], serde_json::json!("foo"));
+        assert_eq!(record.fields["b"], serde_json::json!("bar"));
+    }
+
+    #[test]
+    fn fixed_records_short_line_yields_missing_value_for_trailing_fields() {
+        let layout = layout(
+            vec![field("a", 1, 3), field("b", 4, 3)],
+            FieldType::Fixed,
+            None,
+        );
+        let mut records = FixedRecords {
+            lines: lines_of("foo\n"),
+            regexes: compile_regexes(&layout).unwrap(),
+            layout: &layout,
+            line_number: 0,
+        };
+
+        let (record, violations) = records.next().unwrap().unwrap();
+        assert!(violations.is_empty());
+        assert_eq!(record.fields["a"], serde_json::json!("foo"));
+        assert_eq!(record.fields["b"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn fixed_records_short_line_reports_violation_for_required_trailing_field() {
+        let mut b = field("b", 4, 3);
+        b.required = Some(true);
+        let layout = layout(vec![field("a", 1, 3), b], FieldType::Fixed, None);
+        let mut records = FixedRecords {
+            lines: lines_of("foo\n"),
+            regexes: compile_regexes(&layout).unwrap(),
+            layout: &layout,
+            line_number: 0,
+        };
+
+        let (_, violations) = records.next().unwrap().unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].field, "b");
+    }
+}
@@ -0,0 +1,32 @@
+/*!
+ * Composite sink that fans out writes to a list of other sinks.
+ */
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use crate::Record;
+
+use super::Sink;
+
+/// Writes every batch to each of its configured `sinks`, in order.
+pub(crate) struct CompositeSink {
+    sinks: Vec<Box<dyn Sink>>,
+}
+
+impl CompositeSink {
+    /// Builds a `CompositeSink` that fans out to `sinks`.
+    pub(crate) fn new(sinks: Vec<Box<dyn Sink>>) -> Self {
+        CompositeSink { sinks }
+    }
+}
+
+#[async_trait]
+impl Sink for CompositeSink {
+    async fn write(&self, records: &[Record]) -> Result<(), Box<dyn Error>> {
+        for sink in &self.sinks {
+            sink.write(records).await?;
+        }
+        Ok(())
+    }
+}
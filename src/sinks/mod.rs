@@ -0,0 +1,74 @@
+/*!
+ * Pluggable write destinations for processed records.
+ *
+ * Each backend implements `Sink` and is only compiled in when its matching
+ * Cargo feature is enabled, so a build that only needs one destination
+ * doesn't pull in `lapin` or the full `mongodb` driver. `build_sink` maps the
+ * `destination` field of a `Layout` to the right implementation, including a
+ * `"both"` composite that fans out to whichever of the RabbitMQ and MongoDB
+ * sinks are compiled in (the SQL sink is not part of it).
+ */
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use crate::{Layout, Record};
+
+#[cfg(feature = "rabbitmq")]
+pub(crate) mod rabbitmq;
+
+#[cfg(feature = "mongodb")]
+pub(crate) mod mongodb;
+
+#[cfg(feature = "sql")]
+pub(crate) mod sql;
+
+pub(crate) mod composite;
+
+/// A destination that a batch of `Record`s can be written to.
+#[async_trait]
+pub(crate) trait Sink: Send + Sync {
+    /// Writes `records` to the destination.
+    async fn write(&self, records: &[Record]) -> Result<(), Box<dyn Error>>;
+}
+
+/// Builds the `Sink` described by `layout.destination`.
+///
+/// # Parameters
+///
+/// - layout: The layout whose `destination` and `storage_name` select the sink.
+///
+/// # Returns
+///
+/// A boxed `Sink` ready to receive records.
+#[allow(clippy::vec_init_then_push)]
+pub(crate) fn build_sink(layout: &Layout) -> Result<Box<dyn Sink>, Box<dyn Error>> {
+    match layout.destination.as_str() {
+        #[cfg(feature = "rabbitmq")]
+        "queue" => Ok(Box::new(rabbitmq::RabbitMqSink::new(&layout.storage_name))),
+
+        #[cfg(feature = "mongodb")]
+        "repository" => Ok(Box::new(mongodb::MongoDbSink::new(&layout.storage_name))),
+
+        #[cfg(feature = "sql")]
+        "sql" => Ok(Box::new(sql::SqlSink::new(layout)?)),
+
+        "both" => {
+            #[allow(unused_mut)]
+            let mut sinks: Vec<Box<dyn Sink>> = Vec::new();
+
+            #[cfg(feature = "rabbitmq")]
+            sinks.push(Box::new(rabbitmq::RabbitMqSink::new(&layout.storage_name)));
+
+            #[cfg(feature = "mongodb")]
+            sinks.push(Box::new(mongodb::MongoDbSink::new(&layout.storage_name)));
+
+            if sinks.is_empty() {
+                return Err("\"both\" destination requested but no sink features are enabled".into());
+            }
+            Ok(Box::new(composite::CompositeSink::new(sinks)))
+        }
+
+        other => Err(format!("Invalid destination specified in config file: {}", other).into()),
+    }
+}
@@ -0,0 +1,67 @@
+/*!
+ * MongoDB sink, compiled in behind the `mongodb` feature.
+ */
+use std::error::Error;
+
+use async_trait::async_trait;
+use log::info;
+use mongodb::bson::Document;
+use mongodb::{options::ClientOptions, Client};
+use tokio::sync::OnceCell;
+
+use crate::Record;
+
+use super::Sink;
+
+/// Inserts each record as a document into a MongoDB collection.
+///
+/// The client is connected once, on the first `write` call, and reused for
+/// every subsequent batch rather than reconnecting per batch.
+pub(crate) struct MongoDbSink {
+    collection_name: String,
+    client: OnceCell<Client>,
+}
+
+impl MongoDbSink {
+    /// Builds a `MongoDbSink` that writes to `collection_name`.
+    pub(crate) fn new(collection_name: &str) -> Self {
+        MongoDbSink {
+            collection_name: collection_name.to_string(),
+            client: OnceCell::new(),
+        }
+    }
+
+    /// Returns the cached client, connecting on first use.
+    async fn client(&self) -> Result<&Client, Box<dyn Error>> {
+        self.client
+            .get_or_try_init(|| async {
+                let client_options =
+                    ClientOptions::parse(&std::env::var("MONGODB_URI").expect("MONGODB_URI not set in .env file"))
+                        .await?;
+                Ok::<_, Box<dyn Error>>(Client::with_options(client_options)?)
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl Sink for MongoDbSink {
+    async fn write(&self, records: &[Record]) -> Result<(), Box<dyn Error>> {
+        let client = self.client().await?;
+        let database = client.database("mydb");
+        let collection = database.collection::<Document>(&self.collection_name);
+
+        let docs: Vec<Document> = records
+            .iter()
+            .map(|record| mongodb::bson::to_document(&record.fields))
+            .collect::<Result<_, _>>()?;
+
+        collection.insert_many(docs, None).await?;
+        info!(
+            "Saved {} record(s) to MongoDB collection: {}",
+            records.len(),
+            self.collection_name
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+/*!
+ * RabbitMQ sink, compiled in behind the `rabbitmq` feature.
+ */
+use std::error::Error;
+
+use async_trait::async_trait;
+use lapin::{options::*, types::FieldTable, BasicProperties, Channel, Connection, ConnectionProperties};
+use log::info;
+use tokio::sync::OnceCell;
+
+use crate::Record;
+
+use super::Sink;
+
+/// Publishes each record as a JSON message to a RabbitMQ queue.
+///
+/// The connection and channel are established once, on the first `write`
+/// call, and reused for every subsequent batch rather than reconnecting
+/// per batch.
+pub(crate) struct RabbitMqSink {
+    queue_name: String,
+    connection: OnceCell<(Connection, Channel)>,
+}
+
+impl RabbitMqSink {
+    /// Builds a `RabbitMqSink` that publishes to `queue_name`.
+    pub(crate) fn new(queue_name: &str) -> Self {
+        RabbitMqSink {
+            queue_name: queue_name.to_string(),
+            connection: OnceCell::new(),
+        }
+    }
+
+    /// Returns the cached channel, connecting and declaring the queue on
+    /// first use.
+    async fn channel(&self) -> Result<&Channel, Box<dyn Error>> {
+        let (_connection, channel) = self
+            .connection
+            .get_or_try_init(|| async {
+                let addr = std::env::var("AMQP_ADDR").expect("AMQP_ADDR not set in .env file");
+                let connection = Connection::connect(&addr, ConnectionProperties::default()).await?;
+                let channel = connection.create_channel().await?;
+                channel
+                    .queue_declare(&self.queue_name, QueueDeclareOptions::default(), FieldTable::default())
+                    .await?;
+                Ok::<_, Box<dyn Error>>((connection, channel))
+            })
+            .await?;
+        Ok(channel)
+    }
+}
+
+#[async_trait]
+impl Sink for RabbitMqSink {
+    async fn write(&self, records: &[Record]) -> Result<(), Box<dyn Error>> {
+        let channel = self.channel().await?;
+
+        for record in records {
+            let json_record = serde_json::to_string(&record.fields)?;
+            channel
+                .basic_publish(
+                    "",
+                    &self.queue_name,
+                    BasicPublishOptions::default(),
+                    json_record.as_bytes(),
+                    BasicProperties::default().with_delivery_mode(1),
+                )
+                .await?;
+        }
+        info!("Sent {} record(s) to RabbitMQ queue: {}", records.len(), self.queue_name);
+        Ok(())
+    }
+}
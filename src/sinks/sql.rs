@@ -0,0 +1,182 @@
+/*!
+ * Relational-database sink, compiled in behind the `sql` feature.
+ *
+ * Maps the `Layout.fields` metadata onto a typed `CREATE TABLE IF NOT EXISTS`
+ * and inserts each record with a parameterized `INSERT`, so flat files can
+ * land directly in Postgres/MySQL rather than Mongo or a queue.
+ */
+use std::env;
+use std::error::Error;
+
+use async_trait::async_trait;
+use log::info;
+use regex::Regex;
+use sqlx::any::{AnyArguments, AnyPoolOptions};
+use sqlx::query::Query;
+use sqlx::{Any, AnyPool};
+use tokio::sync::OnceCell;
+
+use crate::{Layout, Record};
+
+use super::Sink;
+
+/// Table/column names are spliced directly into DDL/DML below (`sqlx`/`Any`
+/// has no identifier-binding support), so they're restricted to this
+/// allowlist rather than quoted — the layout they come from may be loaded
+/// from a remote URL, and a name like `x); DROP TABLE orders;--` would
+/// otherwise be SQL injection.
+fn validate_identifier(name: &str) -> Result<(), Box<dyn Error>> {
+    let pattern = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap();
+    if pattern.is_match(name) {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a valid SQL identifier", name).into())
+    }
+}
+
+/// Inserts each record into a SQL table derived from the layout's fields.
+pub(crate) struct SqlSink {
+    table_name: String,
+    columns: Vec<(String, String)>,
+    pool: OnceCell<(AnyPool, Placeholders)>,
+}
+
+impl SqlSink {
+    /// Builds a `SqlSink` for `layout.storage_name`, with one column per
+    /// `layout.fields` entry.
+    ///
+    /// # Returns
+    ///
+    /// An error if `layout.storage_name` or any field name isn't a valid SQL
+    /// identifier, since both are spliced directly into the DDL/DML below.
+    pub(crate) fn new(layout: &Layout) -> Result<Self, Box<dyn Error>> {
+        validate_identifier(&layout.storage_name)?;
+
+        let columns = layout
+            .fields
+            .iter()
+            .map(|field| {
+                validate_identifier(&field.name)?;
+                Ok((field.name.clone(), field.field_type.clone()))
+            })
+            .collect::<Result<_, Box<dyn Error>>>()?;
+
+        Ok(SqlSink {
+            table_name: layout.storage_name.clone(),
+            columns,
+            pool: OnceCell::new(),
+        })
+    }
+
+    /// Returns the cached pool and its placeholder style, connecting and
+    /// creating the table on first use.
+    async fn pool(&self) -> Result<&(AnyPool, Placeholders), Box<dyn Error>> {
+        self.pool
+            .get_or_try_init(|| async {
+                let database_url = env::var("DATABASE_URL").expect("DATABASE_URL not set in .env file");
+                let placeholders = Placeholders::for_url(&database_url);
+                sqlx::any::install_default_drivers();
+                let pool = AnyPoolOptions::new().connect(&database_url).await?;
+
+                let column_defs: Vec<String> = self
+                    .columns
+                    .iter()
+                    .map(|(name, field_type)| format!("{} {}", name, sql_type_for(field_type)))
+                    .collect();
+                let create_stmt = format!(
+                    "CREATE TABLE IF NOT EXISTS {} ({})",
+                    self.table_name,
+                    column_defs.join(", ")
+                );
+                sqlx::query(&create_stmt).execute(&pool).await?;
+
+                Ok::<_, Box<dyn Error>>((pool, placeholders))
+            })
+            .await
+    }
+}
+
+/// Bind-parameter placeholder style for the connection's backend.
+/// `sqlx::Any` binds either style but does not translate between them, so
+/// the sink has to build the `INSERT` with the style the underlying driver
+/// expects: numbered `$1, $2, ...` for Postgres, positional `?` for
+/// everything else (notably MySQL).
+#[derive(Clone, Copy)]
+enum Placeholders {
+    Numbered,
+    Positional,
+}
+
+impl Placeholders {
+    fn for_url(database_url: &str) -> Self {
+        if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+            Placeholders::Numbered
+        } else {
+            Placeholders::Positional
+        }
+    }
+
+    /// Builds the `VALUES (...)` placeholder list for `count` columns.
+    fn list(self, count: usize) -> String {
+        match self {
+            Placeholders::Numbered => (1..=count).map(|i| format!("${}", i)).collect::<Vec<_>>().join(", "),
+            Placeholders::Positional => vec!["?"; count].join(", "),
+        }
+    }
+}
+
+/// Maps a layout `field_type` to a SQL column type.
+fn sql_type_for(field_type: &str) -> &'static str {
+    match field_type {
+        "int" => "BIGINT",
+        "float" => "DOUBLE PRECISION",
+        "bool" => "BOOLEAN",
+        _ => "TEXT",
+    }
+}
+
+/// Binds a record's value for `field_type` onto `query`, choosing the bind
+/// type from the layout rather than the JSON value's own shape. A value whose
+/// JSON shape doesn't match that type is bound as `NULL`, not converted.
+fn bind_value<'q>(
+    query: Query<'q, Any, AnyArguments<'q>>,
+    field_type: &str,
+    value: Option<&'q serde_json::Value>,
+) -> Query<'q, Any, AnyArguments<'q>> {
+    match field_type {
+        "int" => query.bind(value.and_then(|v| v.as_i64())),
+        "float" => query.bind(value.and_then(|v| v.as_f64())),
+        "bool" => query.bind(value.and_then(|v| v.as_bool())),
+        _ => query.bind(value.and_then(|v| v.as_str()).map(|s| s.to_string())),
+    }
+}
+
+#[async_trait]
+impl Sink for SqlSink {
+    async fn write(&self, records: &[Record]) -> Result<(), Box<dyn Error>> {
+        let (pool, placeholders) = self.pool().await?;
+
+        let column_names: Vec<&str> = self.columns.iter().map(|(name, _)| name.as_str()).collect();
+        let insert_stmt = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            self.table_name,
+            column_names.join(", "),
+            placeholders.list(column_names.len())
+        );
+
+        for record in records {
+            let mut query = sqlx::query(&insert_stmt);
+            for (name, field_type) in &self.columns {
+                query = bind_value(query, field_type, record.fields.get(name));
+            }
+            query.execute(pool).await?;
+        }
+
+        info!(
+            "Inserted {} records into SQL table: {}",
+            records.len(),
+            self.table_name
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+/*!
+ * Source abstraction for the CLI arguments.
+ *
+ * The layout and data arguments can be local paths, `file://` URLs, or
+ * `http(s)://` URLs. Local files are streamed line-by-line as before; remote
+ * locations are fetched with `reqwest` and exposed through the same `Read`/
+ * `BufRead` interfaces so callers don't need to know where the bytes came from.
+ */
+use std::error::Error;
+use std::fs;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+use reqwest::Url;
+
+/// Opens `location` for reading, returning a boxed `Read` regardless of
+/// whether it resolves to a local file or a remote HTTP(S) response.
+///
+/// The HTTP(S) fetch runs on `spawn_blocking`: `reqwest::blocking` builds and
+/// blocks on its own runtime internally, which panics if called directly
+/// from a thread already driving a Tokio runtime (as every call site here
+/// is, via `#[tokio::main]`).
+///
+/// # Parameters
+///
+/// - location: A local path, `file://` URL, or `http(s)://` URL.
+///
+/// # Example
+///
+/// ```
+/// let reader = open_source(&data_file).await?;
+/// ```
+///
+pub(crate) async fn open_source(location: &str) -> Result<Box<dyn Read + Send>, Box<dyn Error>> {
+    if let Ok(url) = Url::parse(location) {
+        match url.scheme() {
+            "http" | "https" => {
+                let response = tokio::task::spawn_blocking(move || {
+                    reqwest::blocking::get(url)?.error_for_status()
+                })
+                .await??;
+                return Ok(Box::new(response));
+            }
+            "file" => {
+                let path = url
+                    .to_file_path()
+                    .map_err(|_| format!("Invalid file URL: {}", location))?;
+                return Ok(Box::new(File::open(path)?));
+            }
+            _ => {}
+        }
+    }
+    Ok(Box::new(File::open(location)?))
+}
+
+/// Opens `location` for buffered, line-by-line reading.
+///
+/// # Parameters
+///
+/// - location: A local path, `file://` URL, or `http(s)://` URL.
+///
+pub(crate) async fn open_buffered(location: &str) -> Result<BufReader<Box<dyn Read + Send>>, Box<dyn Error>> {
+    Ok(BufReader::new(open_source(location).await?))
+}
+
+/// Reads the full contents of `location` into a `String`, whether it is a
+/// local path, a `file://` URL, or a remote HTTP(S) URL.
+///
+/// Like `open_source`, the HTTP(S) fetch runs on `spawn_blocking` so the
+/// blocking `reqwest` client doesn't try to nest its own runtime inside the
+/// Tokio worker thread that's already running it.
+///
+/// # Parameters
+///
+/// - location: A local path, `file://` URL, or `http(s)://` URL.
+///
+pub(crate) async fn read_source_to_string(location: &str) -> Result<String, Box<dyn Error>> {
+    if let Ok(url) = Url::parse(location) {
+        match url.scheme() {
+            "http" | "https" => {
+                let text = tokio::task::spawn_blocking(move || {
+                    reqwest::blocking::get(url)?.error_for_status()?.text()
+                })
+                .await??;
+                return Ok(text);
+            }
+            "file" => {
+                let path = url
+                    .to_file_path()
+                    .map_err(|_| format!("Invalid file URL: {}", location))?;
+                return Ok(fs::read_to_string(path)?);
+            }
+            _ => {}
+        }
+    }
+    Ok(fs::read_to_string(location)?)
+}
@@ -0,0 +1,271 @@
+/*!
+ * Field-level validation and transformation, driven by the layout.
+ *
+ * Replaces the old `unwrap_or(0)`/`unwrap_or(false)` coercion with rules
+ * carried on each `Field` (`required`, `regex`, `min`/`max`, `default`), so a
+ * malformed value produces a structured `ValidationError` instead of a
+ * silently wrong `0`.
+ */
+use regex::Regex;
+use serde_json::{Number, Value};
+
+use crate::Field;
+
+/// A single field-level violation, tied to the line it came from.
+#[derive(Debug)]
+pub(crate) struct ValidationError {
+    pub(crate) line: usize,
+    pub(crate) field: String,
+    pub(crate) reason: String,
+}
+
+impl ValidationError {
+    fn new(line: usize, field: &str, reason: impl Into<String>) -> Self {
+        ValidationError {
+            line,
+            field: field.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Compiles `field.regex`, if declared, once per field so callers can reuse
+/// it across every record instead of recompiling it per line.
+///
+/// # Parameters
+///
+/// - field: The layout field whose `regex` (if any) should be compiled.
+///
+/// # Returns
+///
+/// `None` if the field declares no pattern, `Some(Ok(regex))` if it compiles,
+/// or `Some(Err(..))` describing why it doesn't.
+pub(crate) fn compile_field_regex(field: &Field) -> Option<Result<Regex, regex::Error>> {
+    field.regex.as_ref().map(|pattern| Regex::new(pattern))
+}
+
+/// Extracts and validates `raw_value` for `field`, applying `required`,
+/// `regex`, `min`/`max`, and `default` as declared on the layout.
+///
+/// # Parameters
+///
+/// - field: The layout field describing the expected type and rules.
+/// - compiled_regex: `field.regex` pre-compiled via `compile_field_regex`,
+///   reused across every record instead of recompiling it per line.
+/// - raw_value: The raw text extracted from the line, if present.
+/// - line_number: 1-based line number, used to report violations.
+///
+/// # Returns
+///
+/// The coerced `Value` on success, or a `ValidationError` describing why the
+/// value was rejected.
+pub(crate) fn extract_field_value(
+    field: &Field,
+    compiled_regex: Option<&Regex>,
+    raw_value: Option<&str>,
+    line_number: usize,
+) -> Result<Value, ValidationError> {
+    let trimmed = raw_value.map(|v| v.trim().trim_matches('"'));
+
+    if trimmed.map(|v| v.is_empty()).unwrap_or(true) {
+        if field.required.unwrap_or(false) {
+            return Err(ValidationError::new(line_number, &field.name, "required field is missing"));
+        }
+        return Ok(field.default.clone().unwrap_or(Value::Null));
+    }
+    let raw = trimmed.unwrap();
+
+    if let Some(re) = compiled_regex {
+        if !re.is_match(raw) {
+            return Err(ValidationError::new(
+                line_number,
+                &field.name,
+                format!("value '{}' does not match pattern '{}'", raw, re.as_str()),
+            ));
+        }
+    }
+
+    match field.field_type.as_str() {
+        "int" => {
+            let parsed: i64 = raw
+                .parse()
+                .map_err(|_| ValidationError::new(line_number, &field.name, format!("'{}' is not a valid int", raw)))?;
+            check_range(field, parsed as f64, line_number)?;
+            Ok(Value::Number(parsed.into()))
+        }
+        "float" => {
+            let parsed: f64 = raw
+                .parse()
+                .map_err(|_| ValidationError::new(line_number, &field.name, format!("'{}' is not a valid float", raw)))?;
+            check_range(field, parsed, line_number)?;
+            Number::from_f64(parsed)
+                .map(Value::Number)
+                .ok_or_else(|| ValidationError::new(line_number, &field.name, format!("'{}' is not a finite float", raw)))
+        }
+        "bool" => {
+            let parsed: bool = raw
+                .parse()
+                .map_err(|_| ValidationError::new(line_number, &field.name, format!("'{}' is not a valid bool", raw)))?;
+            Ok(Value::Bool(parsed))
+        }
+        _ => Ok(Value::String(raw.to_string())),
+    }
+}
+
+/// Checks `value` against `field.min`/`field.max`, if declared.
+fn check_range(field: &Field, value: f64, line_number: usize) -> Result<(), ValidationError> {
+    if let Some(min) = field.min {
+        if value < min {
+            return Err(ValidationError::new(
+                line_number,
+                &field.name,
+                format!("{} is below the minimum of {}", value, min),
+            ));
+        }
+    }
+    if let Some(max) = field.max {
+        if value > max {
+            return Err(ValidationError::new(
+                line_number,
+                &field.name,
+                format!("{} is above the maximum of {}", value, max),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(field_type: &str) -> Field {
+        Field {
+            name: "amount".to_string(),
+            description: String::new(),
+            position: 1,
+            size: 10,
+            field_type: field_type.to_string(),
+            required: None,
+            regex: None,
+            min: None,
+            max: None,
+            default: None,
+        }
+    }
+
+    #[test]
+    fn missing_optional_field_returns_default() {
+        let mut f = field("string");
+        f.default = Some(Value::String("n/a".to_string()));
+        assert_eq!(extract_field_value(&f, None, None, 1).unwrap(), Value::String("n/a".to_string()));
+    }
+
+    #[test]
+    fn missing_optional_field_without_default_returns_null() {
+        let f = field("string");
+        assert_eq!(extract_field_value(&f, None, None, 1).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn missing_required_field_is_rejected() {
+        let mut f = field("string");
+        f.required = Some(true);
+        let err = extract_field_value(&f, None, None, 7).unwrap_err();
+        assert_eq!(err.line, 7);
+        assert_eq!(err.field, "amount");
+    }
+
+    #[test]
+    fn blank_value_is_treated_as_missing() {
+        let f = field("string");
+        assert_eq!(extract_field_value(&f, None, Some("   "), 1).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn int_field_parses_and_trims_quotes() {
+        let f = field("int");
+        assert_eq!(extract_field_value(&f, None, Some("\"42\""), 1).unwrap(), Value::Number(42.into()));
+    }
+
+    #[test]
+    fn int_field_rejects_non_numeric_value() {
+        let f = field("int");
+        let err = extract_field_value(&f, None, Some("abc"), 1).unwrap_err();
+        assert!(err.reason.contains("not a valid int"));
+    }
+
+    #[test]
+    fn bool_field_parses() {
+        let f = field("bool");
+        assert_eq!(extract_field_value(&f, None, Some("true"), 1).unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn unknown_field_type_is_kept_as_string() {
+        let f = field("date");
+        assert_eq!(
+            extract_field_value(&f, None, Some("hello"), 1).unwrap(),
+            Value::String("hello".to_string())
+        );
+    }
+
+    #[test]
+    fn value_failing_regex_is_rejected() {
+        let f = field("string");
+        let re = Regex::new(r"^\d+$").unwrap();
+        let err = extract_field_value(&f, Some(&re), Some("abc"), 1).unwrap_err();
+        assert!(err.reason.contains("does not match pattern"));
+    }
+
+    #[test]
+    fn value_matching_regex_is_accepted() {
+        let f = field("string");
+        let re = Regex::new(r"^\d+$").unwrap();
+        assert_eq!(extract_field_value(&f, Some(&re), Some("123"), 1).unwrap(), Value::String("123".to_string()));
+    }
+
+    #[test]
+    fn value_below_minimum_is_rejected() {
+        let mut f = field("int");
+        f.min = Some(10.0);
+        let err = extract_field_value(&f, None, Some("5"), 1).unwrap_err();
+        assert!(err.reason.contains("below the minimum"));
+    }
+
+    #[test]
+    fn value_above_maximum_is_rejected() {
+        let mut f = field("float");
+        f.max = Some(10.0);
+        let err = extract_field_value(&f, None, Some("10.5"), 1).unwrap_err();
+        assert!(err.reason.contains("above the maximum"));
+    }
+
+    #[test]
+    fn value_within_range_is_accepted() {
+        let mut f = field("int");
+        f.min = Some(0.0);
+        f.max = Some(10.0);
+        assert_eq!(extract_field_value(&f, None, Some("5"), 1).unwrap(), Value::Number(5.into()));
+    }
+
+    #[test]
+    fn compile_field_regex_returns_none_without_pattern() {
+        let f = field("string");
+        assert!(compile_field_regex(&f).is_none());
+    }
+
+    #[test]
+    fn compile_field_regex_compiles_valid_pattern() {
+        let mut f = field("string");
+        f.regex = Some(r"^\d+$".to_string());
+        assert!(compile_field_regex(&f).unwrap().is_ok());
+    }
+
+    #[test]
+    fn compile_field_regex_reports_invalid_pattern() {
+        let mut f = field("string");
+        f.regex = Some("(".to_string());
+        assert!(compile_field_regex(&f).unwrap().is_err());
+    }
+}